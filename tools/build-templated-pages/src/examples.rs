@@ -1,13 +1,144 @@
 use core::cmp::Ordering;
+use core::str::FromStr;
 use std::fs::File;
 
 use hashbrown::HashMap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
-use toml_edit::{DocumentMut, Item};
+use toml_edit::DocumentMut;
 
 use crate::Command;
 
+/// Output format for the [`Diagnostic`]s reported by `check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageFormat {
+    /// Multi-line, human-readable text (the default).
+    Human,
+    /// A single machine-parsable JSON array of [`Diagnostic`]s.
+    Json,
+    /// One line per problem, suitable for quickly scanning a terminal.
+    Short,
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            "short" => Ok(MessageFormat::Short),
+            other => Err(format!(
+                "unknown message format `{other}`, expected one of: human, json, short"
+            )),
+        }
+    }
+}
+
+/// A single validation failure found while parsing the example metadata in `Cargo.toml`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct Diagnostic {
+    example: String,
+    path: String,
+    problem: String,
+}
+
+impl Diagnostic {
+    fn new(
+        example: impl Into<String>,
+        path: impl Into<String>,
+        problem: impl Into<String>,
+    ) -> Self {
+        Diagnostic {
+            example: example.into(),
+            path: path.into(),
+            problem: problem.into(),
+        }
+    }
+}
+
+/// Prints `diagnostics` in `format` and returns whether any were reported.
+fn emit_diagnostics(diagnostics: &[Diagnostic], format: MessageFormat) -> bool {
+    if diagnostics.is_empty() {
+        return false;
+    }
+
+    match format {
+        MessageFormat::Human => {
+            eprintln!(
+                "Found {} problem(s) with example metadata:\n",
+                diagnostics.len()
+            );
+            for diagnostic in diagnostics {
+                eprintln!(
+                    "error: {}\n  --> {}\n  {}\n",
+                    diagnostic.example, diagnostic.path, diagnostic.problem
+                );
+            }
+        }
+        MessageFormat::Short => {
+            for diagnostic in diagnostics {
+                eprintln!(
+                    "{}: {}: {}",
+                    diagnostic.path, diagnostic.example, diagnostic.problem
+                );
+            }
+        }
+        MessageFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string(diagnostics).expect("diagnostics are always serializable")
+            );
+        }
+    }
+
+    true
+}
+
+/// A `[[example]]` entry in `Cargo.toml`, the part `cargo` itself understands.
+#[derive(Debug, Deserialize)]
+struct ExampleTarget {
+    name: String,
+    path: String,
+    #[serde(rename = "doc-scrape-examples")]
+    doc_scrape_examples: Option<bool>,
+}
+
+/// One entry of `[package.metadata.example]`, keyed by the example's technical name.
+///
+/// `screenshot`, `tags` and `difficulty` are optional front matter: they aren't used to decide
+/// whether an example is valid, but they flow into the Tera context so templates can render them.
+#[derive(Debug, Deserialize)]
+struct ExampleMetadata {
+    name: String,
+    description: String,
+    category: String,
+    wasm: bool,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    screenshot: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    difficulty: Option<String>,
+}
+
+/// One entry of `[[package.metadata.example_category]]`.
+#[derive(Debug, Deserialize)]
+struct CategoryMetadata {
+    name: String,
+    description: String,
+}
+
+/// Deserializes a `toml_edit` table into a typed struct, re-serializing it to text first since
+/// `toml_edit` only implements `serde::Deserialize` for whole documents.
+fn deserialize_toml<T: for<'de> Deserialize<'de>>(
+    table: impl std::fmt::Display,
+) -> Result<T, String> {
+    toml_edit::de::from_str(&table.to_string()).map_err(|error| error.to_string())
+}
+
 #[derive(Debug, Serialize, PartialEq, Eq)]
 struct Category {
     description: Option<String>,
@@ -22,6 +153,9 @@ struct Example {
     description: String,
     category: String,
     wasm: bool,
+    screenshot: Option<String>,
+    tags: Vec<String>,
+    difficulty: Option<String>,
 }
 
 impl Ord for Example {
@@ -36,78 +170,173 @@ impl PartialOrd for Example {
     }
 }
 
-fn parse_examples(panic_on_missing: bool) -> Vec<Example> {
-    let manifest_file = std::fs::read_to_string("Cargo.toml").unwrap();
-    let manifest = manifest_file.parse::<DocumentMut>().unwrap();
-    let metadatas = manifest
-        .get("package")
-        .unwrap()
-        .get("metadata")
-        .as_ref()
-        .unwrap()["example"]
-        .clone();
+fn parse_examples(
+    manifest: &DocumentMut,
+    check_missing: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Example> {
+    let metadatas = manifest["package"]["metadata"]["example"].clone();
 
     manifest["example"]
         .as_array_of_tables()
         .unwrap()
         .iter()
-        .flat_map(|val| {
-            let technical_name = val.get("name").unwrap().as_str().unwrap().to_string();
-            if panic_on_missing && metadatas.get(&technical_name).is_none() {
-                panic!("Missing metadata for example {technical_name}");
+        .flat_map(|table| {
+            let target: ExampleTarget = match deserialize_toml(table) {
+                Ok(target) => target,
+                Err(problem) => {
+                    let name = table
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("<unknown>");
+                    let path = table
+                        .get("path")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("<unknown>");
+                    diagnostics.push(Diagnostic::new(name, path, problem));
+                    return None;
+                }
+            };
+
+            let metadata_item = metadatas.get(&target.name);
+
+            if check_missing && metadata_item.is_none() {
+                diagnostics.push(Diagnostic::new(
+                    &target.name,
+                    &target.path,
+                    "missing metadata entry in [package.metadata.example]",
+                ));
+                return None;
             }
-            if panic_on_missing && val.get("doc-scrape-examples").is_none() {
-                panic!("Example {technical_name} is missing doc-scrape-examples");
+            if check_missing && target.doc_scrape_examples.is_none() {
+                diagnostics.push(Diagnostic::new(
+                    &target.name,
+                    &target.path,
+                    "missing `doc-scrape-examples` key",
+                ));
             }
 
-            if metadatas
-                .get(&technical_name)
-                .and_then(|metadata| metadata.get("hidden"))
-                .and_then(Item::as_bool)
-                .unwrap_or(false)
-            {
+            let metadata: ExampleMetadata = match deserialize_toml(metadata_item?) {
+                Ok(metadata) => metadata,
+                Err(problem) => {
+                    diagnostics.push(Diagnostic::new(&target.name, &target.path, problem));
+                    return None;
+                }
+            };
+
+            if metadata.hidden {
                 return None;
             }
 
-            metadatas.get(&technical_name).map(|metadata| Example {
-                technical_name,
-                path: val["path"].as_str().unwrap().to_string(),
-                name: metadata["name"].as_str().unwrap().to_string(),
-                description: metadata["description"].as_str().unwrap().to_string(),
-                category: metadata["category"].as_str().unwrap().to_string(),
-                wasm: metadata["wasm"].as_bool().unwrap(),
+            Some(Example {
+                technical_name: target.name,
+                path: target.path,
+                name: metadata.name,
+                description: metadata.description,
+                category: metadata.category,
+                wasm: metadata.wasm,
+                screenshot: metadata.screenshot,
+                tags: metadata.tags,
+                difficulty: metadata.difficulty,
             })
         })
         .collect()
 }
 
-fn parse_categories() -> HashMap<Box<str>, String> {
-    let manifest_file = std::fs::read_to_string("Cargo.toml").unwrap();
-    let manifest = manifest_file.parse::<DocumentMut>().unwrap();
-    manifest
-        .get("package")
-        .unwrap()
-        .get("metadata")
-        .as_ref()
-        .unwrap()["example_category"]
-        .clone()
+fn parse_categories(
+    manifest: &DocumentMut,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> HashMap<Box<str>, String> {
+    manifest["package"]["metadata"]["example_category"]
         .as_array_of_tables()
         .unwrap()
         .iter()
-        .map(|v| {
-            (
-                v.get("name").unwrap().as_str().unwrap().into(),
-                v.get("description").unwrap().as_str().unwrap().to_string(),
-            )
+        .filter_map(|table| match deserialize_toml::<CategoryMetadata>(table) {
+            Ok(category) => Some((category.name.into_boxed_str(), category.description)),
+            Err(problem) => {
+                diagnostics.push(Diagnostic::new("<example_category>", "Cargo.toml", problem));
+                None
+            }
         })
         .collect()
 }
 
-pub(crate) fn check(what_to_run: Command) {
-    let examples = parse_examples(what_to_run.contains(Command::CHECK_MISSING));
+/// Reports `[package.metadata.example]` entries that don't correspond to any `[[example]]`
+/// target, which otherwise linger silently after an example is renamed or deleted.
+fn check_orphaned_metadata(manifest: &DocumentMut, diagnostics: &mut Vec<Diagnostic>) {
+    let known_targets: std::collections::HashSet<&str> = manifest["example"]
+        .as_array_of_tables()
+        .unwrap()
+        .iter()
+        .filter_map(|table| table.get("name").and_then(|name| name.as_str()))
+        .collect();
+
+    let metadatas = manifest["package"]["metadata"]["example"]
+        .as_table_like()
+        .expect("[package.metadata.example] must be a table");
+
+    for (technical_name, _) in metadatas.iter() {
+        if !known_targets.contains(technical_name) {
+            diagnostics.push(Diagnostic::new(
+                technical_name,
+                "Cargo.toml",
+                "metadata entry has no matching [[example]] target (renamed or deleted example?)",
+            ));
+        }
+    }
+}
+
+/// Reports examples whose `category` wasn't declared in `[package.metadata.example_category]`,
+/// and examples that set `wasm = true` but whose source has no wasm-conditional handling.
+fn check_example_consistency(
+    examples: &[Example],
+    categories: &HashMap<Box<str>, String>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for example in examples {
+        if !categories.contains_key(example.category.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                &example.technical_name,
+                &example.path,
+                format!(
+                    "category `{}` is not declared in [package.metadata.example_category]",
+                    example.category
+                ),
+            ));
+        }
+
+        if example.wasm {
+            let source = std::fs::read_to_string(&example.path).unwrap_or_default();
+            if !source.contains("wasm32") {
+                diagnostics.push(Diagnostic::new(
+                    &example.technical_name,
+                    &example.path,
+                    "metadata sets `wasm = true` but the source has no wasm-conditional handling (no `wasm32` reference found)",
+                ));
+            }
+        }
+    }
+}
+
+pub(crate) fn check(what_to_run: Command, message_format: MessageFormat) {
+    let manifest_file = std::fs::read_to_string("Cargo.toml").unwrap();
+    let manifest = manifest_file.parse::<DocumentMut>().unwrap();
+
+    let mut diagnostics = Vec::new();
+    let check_missing = what_to_run.contains(Command::CHECK_MISSING);
+    let examples = parse_examples(&manifest, check_missing, &mut diagnostics);
+    let categories = parse_categories(&manifest, &mut diagnostics);
+
+    if check_missing {
+        check_orphaned_metadata(&manifest, &mut diagnostics);
+        check_example_consistency(&examples, &categories, &mut diagnostics);
+    }
+
+    if emit_diagnostics(&diagnostics, message_format) {
+        std::process::exit(1);
+    }
 
-    if what_to_run.contains(Command::UPDATE) {
-        let categories = parse_categories();
+    if what_to_run.intersects(Command::UPDATE | Command::JSON) {
         let examples_by_category: HashMap<Box<str>, Category> = examples
             .into_iter()
             .fold(HashMap::<Box<str>, Vec<Example>>::new(), |mut v, ex| {
@@ -128,15 +357,317 @@ pub(crate) fn check(what_to_run: Command) {
             })
             .collect();
 
+        if what_to_run.contains(Command::UPDATE) {
+            render_site(&examples_by_category);
+        }
+
+        if what_to_run.contains(Command::JSON) {
+            write_examples_catalog(&examples_by_category);
+        }
+    }
+}
+
+const DOCS_TEMPLATE_GLOB: &str = "docs-template/*.md.tpl";
+
+/// How a given template name should be rendered.
+#[derive(Debug, PartialEq, Eq)]
+enum TemplateMode {
+    /// Once per category, with `category`/`description`/`examples` in context.
+    Category,
+    /// Once, with `examples` in context filtered down to `wasm == true`.
+    WasmIndex,
+    /// Once, with the full `all_examples` category map in context.
+    Default,
+}
+
+fn template_mode(template_name: &str) -> TemplateMode {
+    match template_name {
+        "CATEGORY.md.tpl" => TemplateMode::Category,
+        "WASM_INDEX.md.tpl" => TemplateMode::WasmIndex,
+        _ => TemplateMode::Default,
+    }
+}
+
+/// Renders every template found by [`DOCS_TEMPLATE_GLOB`] under `examples/`: `CATEGORY.md.tpl`
+/// to one file per category in `examples/categories/`, every other template to a single file.
+fn render_site(examples_by_category: &HashMap<Box<str>, Category>) {
+    let tera = Tera::new(DOCS_TEMPLATE_GLOB).expect("error parsing template");
+
+    for template_name in tera.get_template_names() {
+        match template_mode(template_name) {
+            TemplateMode::Category => render_category_pages(&tera, examples_by_category),
+            TemplateMode::WasmIndex => render_wasm_index(&tera, examples_by_category),
+            TemplateMode::Default => render_default(&tera, template_name, examples_by_category),
+        }
+    }
+}
+
+/// `EXAMPLE_README.md.tpl` keeps its established output path; every other template is named
+/// after itself.
+fn output_path_for_template(template_name: &str) -> String {
+    if template_name == "EXAMPLE_README.md.tpl" {
+        return "examples/README.md".to_string();
+    }
+    format!(
+        "examples/{}",
+        template_name.strip_suffix(".tpl").unwrap_or(template_name)
+    )
+}
+
+fn render_default(
+    tera: &Tera,
+    template_name: &str,
+    examples_by_category: &HashMap<Box<str>, Category>,
+) {
+    let mut context = Context::new();
+    context.insert("all_examples", examples_by_category);
+    render_to_path(
+        tera,
+        template_name,
+        &context,
+        &output_path_for_template(template_name),
+    );
+}
+
+fn render_category_pages(tera: &Tera, examples_by_category: &HashMap<Box<str>, Category>) {
+    for (category, data) in examples_by_category {
         let mut context = Context::new();
-        context.insert("all_examples", &examples_by_category);
-        Tera::new("docs-template/*.md.tpl")
-            .expect("error parsing template")
-            .render_to(
-                "EXAMPLE_README.md.tpl",
-                &context,
-                File::create("examples/README.md").expect("error creating file"),
-            )
-            .expect("error rendering template");
+        context.insert("category", category);
+        context.insert("description", &data.description);
+        context.insert("examples", &data.examples);
+
+        let slug = category.to_lowercase().replace(' ', "-");
+        render_to_path(
+            tera,
+            "CATEGORY.md.tpl",
+            &context,
+            &format!("examples/categories/{slug}.md"),
+        );
+    }
+}
+
+fn render_wasm_index(tera: &Tera, examples_by_category: &HashMap<Box<str>, Category>) {
+    let mut wasm_examples: Vec<&Example> = examples_by_category
+        .values()
+        .flat_map(|category| category.examples.iter())
+        .filter(|example| example.wasm)
+        .collect();
+    wasm_examples.sort();
+
+    let mut context = Context::new();
+    context.insert("examples", &wasm_examples);
+    render_to_path(
+        tera,
+        "WASM_INDEX.md.tpl",
+        &context,
+        "examples/WASM_EXAMPLES.md",
+    );
+}
+
+fn render_to_path(tera: &Tera, template_name: &str, context: &Context, path: &str) {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(parent).expect("error creating output directory");
+    }
+    tera.render_to(
+        template_name,
+        context,
+        File::create(path).expect("error creating file"),
+    )
+    .expect("error rendering template");
+}
+
+/// Writes the same `examples_by_category` data the README template consumes out as a
+/// machine-readable JSON catalog, sorted by category so the output is deterministic.
+fn write_examples_catalog(examples_by_category: &HashMap<Box<str>, Category>) {
+    let json = build_catalog_json(examples_by_category);
+    std::fs::write("examples/examples.json", json).expect("error writing examples.json");
+}
+
+#[derive(Serialize)]
+struct CategoryCatalogEntry<'a> {
+    category: &'a str,
+    description: &'a Option<String>,
+    examples: &'a [Example],
+}
+
+/// Builds the `examples.json` contents: the categories of `examples_by_category`, sorted by
+/// name so the output is deterministic across runs.
+fn build_catalog_json(examples_by_category: &HashMap<Box<str>, Category>) -> String {
+    let mut catalog: Vec<_> = examples_by_category
+        .iter()
+        .map(|(category, data)| CategoryCatalogEntry {
+            category,
+            description: &data.description,
+            examples: &data.examples,
+        })
+        .collect();
+    catalog.sort_by(|a, b| a.category.cmp(b.category));
+
+    serde_json::to_string_pretty(&catalog).expect("catalog is always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_format_parses_known_values() {
+        assert_eq!("human".parse(), Ok(MessageFormat::Human));
+        assert_eq!("json".parse(), Ok(MessageFormat::Json));
+        assert_eq!("short".parse(), Ok(MessageFormat::Short));
+        assert!("weird".parse::<MessageFormat>().is_err());
+    }
+
+    #[test]
+    fn emit_diagnostics_reports_whether_any_were_found() {
+        assert!(!emit_diagnostics(&[], MessageFormat::Human));
+        assert!(emit_diagnostics(
+            &[Diagnostic::new("foo", "examples/foo.rs", "bad")],
+            MessageFormat::Human
+        ));
+    }
+
+    #[test]
+    fn parse_examples_keeps_name_and_path_when_target_is_malformed() {
+        let manifest: DocumentMut = r#"
+[[example]]
+name = "foo"
+path = "examples/foo.rs"
+doc-scrape-examples = "not-a-bool"
+
+[package.metadata.example.foo]
+name = "Foo"
+description = "desc"
+category = "2D"
+wasm = false
+"#
+        .parse()
+        .unwrap();
+
+        let mut diagnostics = Vec::new();
+        let examples = parse_examples(&manifest, true, &mut diagnostics);
+
+        assert!(examples.is_empty());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].example, "foo");
+        assert_eq!(diagnostics[0].path, "examples/foo.rs");
+    }
+
+    #[test]
+    fn check_orphaned_metadata_flags_entries_with_no_matching_target() {
+        let manifest: DocumentMut = r#"
+[[example]]
+name = "foo"
+path = "examples/foo.rs"
+
+[package.metadata.example.foo]
+name = "Foo"
+description = "desc"
+category = "2D"
+wasm = false
+
+[package.metadata.example.stale]
+name = "Stale"
+description = "desc"
+category = "2D"
+wasm = false
+"#
+        .parse()
+        .unwrap();
+
+        let mut diagnostics = Vec::new();
+        check_orphaned_metadata(&manifest, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].example, "stale");
+    }
+
+    fn example(technical_name: &str, path: &str, category: &str, wasm: bool) -> Example {
+        Example {
+            technical_name: technical_name.to_string(),
+            path: path.to_string(),
+            name: technical_name.to_string(),
+            description: "desc".to_string(),
+            category: category.to_string(),
+            wasm,
+            screenshot: None,
+            tags: Vec::new(),
+            difficulty: None,
+        }
+    }
+
+    #[test]
+    fn check_example_consistency_flags_unknown_category() {
+        let examples = [example("foo", "examples/foo.rs", "Unknown", false)];
+        let categories = HashMap::new();
+
+        let mut diagnostics = Vec::new();
+        check_example_consistency(&examples, &categories, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].problem.contains("Unknown"));
+    }
+
+    #[test]
+    fn check_example_consistency_flags_wasm_without_wasm32_handling() {
+        let examples = [example("foo", "examples/does-not-exist.rs", "2D", true)];
+        let mut categories = HashMap::new();
+        categories.insert("2D".into(), "desc".to_string());
+
+        let mut diagnostics = Vec::new();
+        check_example_consistency(&examples, &categories, &mut diagnostics);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].problem.contains("wasm32"));
+    }
+
+    #[test]
+    fn build_catalog_json_sorts_categories_by_name() {
+        let mut examples_by_category = HashMap::new();
+        examples_by_category.insert(
+            "Window".into(),
+            Category {
+                description: None,
+                examples: vec![example("window", "examples/window.rs", "Window", false)],
+            },
+        );
+        examples_by_category.insert(
+            "2D".into(),
+            Category {
+                description: Some("2D examples".to_string()),
+                examples: vec![example("sprite", "examples/sprite.rs", "2D", false)],
+            },
+        );
+
+        let json = build_catalog_json(&examples_by_category);
+        let two_d_index = json.find("\"2D\"").expect("2D category present");
+        let window_index = json.find("\"Window\"").expect("Window category present");
+        assert!(two_d_index < window_index);
+    }
+
+    #[test]
+    fn template_mode_dispatches_builtins_and_defaults_the_rest() {
+        assert_eq!(template_mode("CATEGORY.md.tpl"), TemplateMode::Category);
+        assert_eq!(template_mode("WASM_INDEX.md.tpl"), TemplateMode::WasmIndex);
+        assert_eq!(
+            template_mode("EXAMPLE_README.md.tpl"),
+            TemplateMode::Default
+        );
+        assert_eq!(
+            template_mode("SOMETHING_ELSE.md.tpl"),
+            TemplateMode::Default
+        );
+    }
+
+    #[test]
+    fn output_path_for_template_keeps_readme_path_and_derives_the_rest() {
+        assert_eq!(
+            output_path_for_template("EXAMPLE_README.md.tpl"),
+            "examples/README.md"
+        );
+        assert_eq!(
+            output_path_for_template("SHOWCASE.md.tpl"),
+            "examples/SHOWCASE.md"
+        );
     }
 }
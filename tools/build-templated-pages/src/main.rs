@@ -0,0 +1,32 @@
+use bitflags::bitflags;
+
+mod examples;
+
+bitflags! {
+    struct Command: u8 {
+        const CHECK_MISSING = 1;
+        const UPDATE = 2;
+        const JSON = 4;
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let what_to_run = args
+        .iter()
+        .fold(Command::empty(), |flags, arg| match arg.as_str() {
+            "check-missing" => flags | Command::CHECK_MISSING,
+            "update" => flags | Command::UPDATE,
+            "json" => flags | Command::JSON,
+            _ => flags,
+        });
+
+    let message_format = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--message-format="))
+        .map(|format| format.parse().expect("invalid --message-format value"))
+        .unwrap_or(examples::MessageFormat::Human);
+
+    examples::check(what_to_run, message_format);
+}